@@ -5,12 +5,11 @@
 //! Types for representing and displaying block sizes.
 use crate::OPT_BLOCKSIZE;
 use clap::ArgMatches;
+use std::ops::{Add, Mul};
+use std::str::FromStr;
 use std::{env, fmt};
 
-use uucore::{
-    display::Quotable,
-    parse_size::{parse_size, ParseSizeError},
-};
+use uucore::{display::Quotable, parse_size::ParseSizeError};
 
 /// The first ten powers of 1024.
 const IEC_BASES: [u128; 10] = [
@@ -45,79 +44,267 @@ const SI_BASES: [u128; 10] = [
 // we use "kB" instead of "KB" because of GNU df
 const SI_SUFFIXES: [&str; 9] = ["B", "kB", "MB", "GB", "TB", "PB", "EB", "ZB", "YB"];
 
-/// Convert a multiple of 1024 into a string like "12K" or "34M".
-///
-/// # Examples
-///
-/// Powers of 1024 become "1K", "1M", "1G", etc.
+/// IEC suffixes for the first nine multi-byte unit suffixes.
+const IEC_SUFFIXES: [&str; 9] = [
+    "B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB", "ZiB", "YiB",
+];
+
+/// Which spelling to use for the powers-of-1024 unit suffix.
+#[derive(Clone, Copy)]
+pub(crate) enum SuffixType {
+    /// GNU `-h` style single-letter suffixes: "K", "M", "G", ...
+    Letter,
+
+    /// IEC suffixes: "KiB", "MiB", "GiB", ...
+    Iec,
+}
+
+/// Long, pluralizable unit names used when [`SizeFormatter::long_units`] is
+/// enabled. Index `0` ("Bytes") is never used directly: the singular/plural
+/// "byte"/"bytes" form is produced separately so that exactly one byte
+/// renders as "1 byte".
+const LONG_SUFFIXES: [&str; 9] = [
+    "Bytes",
+    "Kilobytes",
+    "Megabytes",
+    "Gigabytes",
+    "Terabytes",
+    "Petabytes",
+    "Exabytes",
+    "Zettabytes",
+    "Yottabytes",
+];
+
+/// A configurable formatter for converting a byte count into a magnitude
+/// and unit suffix.
 ///
-/// ```rust,ignore
-/// assert_eq!(to_magnitude_and_suffix_1024(1024).unwrap(), "1K");
-/// assert_eq!(to_magnitude_and_suffix_1024(1024 * 1024).unwrap(), "1M");
-/// assert_eq!(to_magnitude_and_suffix_1024(1024 * 1024 * 1024).unwrap(), "1G");
-/// ```
+/// This generalizes the divisor (1,000 or 1,024), the number of decimal
+/// places, the separator between the magnitude and the unit, and whether
+/// to use long unit names, mirroring the options offered by common
+/// byte-formatting ("humansize"-style) libraries.
 ///
-/// Multiples of those powers affect the magnitude part of the
-/// returned string:
+/// # Examples
 ///
 /// ```rust,ignore
-/// assert_eq!(to_magnitude_and_suffix_1024(123 * 1024).unwrap(), "123K");
-/// assert_eq!(to_magnitude_and_suffix_1024(456 * 1024 * 1024).unwrap(), "456M");
-/// assert_eq!(to_magnitude_and_suffix_1024(789 * 1024 * 1024 * 1024).unwrap(), "789G");
+/// let formatter = SizeFormatter::new().base(1000).precision(3).separator(" ");
+/// assert_eq!(formatter.format(1_049_000).unwrap(), "1.049 MB");
 /// ```
-fn to_magnitude_and_suffix_1024(n: u128) -> Result<String, ()> {
-    // Find the smallest power of 1024 that is larger than `n`. That
-    // number indicates which units and suffix to use.
-    for i in 0..IEC_BASES.len() - 1 {
-        if n < IEC_BASES[i + 1] {
-            return Ok(format!("{}{}", n / IEC_BASES[i], SUFFIXES[i]));
+#[derive(Clone, Copy)]
+pub(crate) struct SizeFormatter {
+    /// The divisor between units: 1,000 or 1,024.
+    base: u128,
+
+    /// The number of decimal places to show.
+    precision: usize,
+
+    /// Placed between the magnitude and the unit suffix.
+    separator: &'static str,
+
+    /// Which spelling to use for the powers-of-1024 unit suffix. Ignored
+    /// when `base` is 1,000 or `long_units` is set.
+    suffix_type: SuffixType,
+
+    /// Use long unit names ("Kilobytes") instead of short ones ("K"/"kB").
+    long_units: bool,
+}
+
+impl SizeFormatter {
+    pub(crate) fn new() -> Self {
+        Self {
+            base: 1024,
+            precision: 1,
+            separator: "",
+            suffix_type: SuffixType::Letter,
+            long_units: false,
         }
     }
-    Err(())
-}
 
-/// Convert a number into a string like "12kB" or "34MB".
-///
-/// Powers of 1000 become "1kB", "1MB", "1GB", etc.
-///
-/// The returned string has a maximum length of 5 chars, for example: "1.1kB", "999kB", "1MB".
-fn to_magnitude_and_suffix_not_powers_of_1024(n: u128) -> Result<String, ()> {
-    let mut i = 0;
+    pub(crate) fn base(mut self, base: u128) -> Self {
+        self.base = base;
+        self
+    }
 
-    while SI_BASES[i + 1] - SI_BASES[i] < n && i < SI_SUFFIXES.len() {
-        i += 1;
+    pub(crate) fn precision(mut self, precision: usize) -> Self {
+        self.precision = precision;
+        self
     }
 
-    let quot = n / SI_BASES[i];
-    let rem = n % SI_BASES[i];
-    let suffix = SI_SUFFIXES[i];
+    pub(crate) fn separator(mut self, separator: &'static str) -> Self {
+        self.separator = separator;
+        self
+    }
 
-    if rem == 0 {
-        Ok(format!("{}{}", quot, suffix))
-    } else {
-        let tenths_place = rem / (SI_BASES[i] / 10);
+    pub(crate) fn suffix_type(mut self, suffix_type: SuffixType) -> Self {
+        self.suffix_type = suffix_type;
+        self
+    }
 
-        if rem % (SI_BASES[i] / 10) == 0 {
-            Ok(format!("{}.{}{}", quot, tenths_place, suffix))
-        } else if tenths_place + 1 == 10 || quot >= 10 {
-            Ok(format!("{}{}", quot + 1, suffix))
+    pub(crate) fn long_units(mut self, long_units: bool) -> Self {
+        self.long_units = long_units;
+        self
+    }
+
+    fn unit_bases(&self) -> [u128; 10] {
+        if self.base == 1024 {
+            IEC_BASES
         } else {
-            Ok(format!("{}.{}{}", quot, tenths_place + 1, suffix))
+            SI_BASES
+        }
+    }
+
+    /// The unit suffix for the unit at index `i`, given the magnitude that
+    /// will be printed next to it (used to pick "byte" vs "bytes").
+    fn unit_suffix(&self, i: usize, magnitude: u128) -> String {
+        if self.long_units {
+            return if i == 0 && magnitude == 1 {
+                "byte".to_string()
+            } else if i == 0 {
+                "bytes".to_string()
+            } else {
+                LONG_SUFFIXES[i].to_string()
+            };
+        }
+        match self.suffix_type {
+            SuffixType::Iec if self.base == 1024 => IEC_SUFFIXES[i].to_string(),
+            _ if self.base == 1024 => SUFFIXES[i].to_string(),
+            _ => SI_SUFFIXES[i].to_string(),
+        }
+    }
+
+    /// Convert `n` bytes into a magnitude and unit suffix using this
+    /// formatter's configuration.
+    ///
+    /// # Errors
+    ///
+    /// If the number is too large to represent.
+    pub(crate) fn format(&self, n: u128) -> Result<String, ()> {
+        let bases = self.unit_bases();
+
+        // Find the largest unit whose base is no larger than `n`.
+        let mut i = bases.len() - 2;
+        for idx in 0..bases.len() - 1 {
+            if n < bases[idx + 1] {
+                i = idx;
+                break;
+            }
+        }
+        let base_i = bases[i];
+
+        // An explicitly requested (non-default) precision is always
+        // honored, even when the value divides evenly and would
+        // otherwise print with no decimal places at all.
+        let explicit_precision = self.precision != 1;
+
+        if n % base_i == 0 {
+            let quot = n / base_i;
+            let suffix = self.unit_suffix(i, quot);
+            return if explicit_precision && self.precision > 0 {
+                Ok(format!(
+                    "{}.{:0width$}{}{}",
+                    quot,
+                    0,
+                    self.separator,
+                    suffix,
+                    width = self.precision
+                ))
+            } else {
+                Ok(format!("{}{}{}", quot, self.separator, suffix))
+            };
+        }
+
+        // GNU df's human-readable output shows at most three significant
+        // digits: a single decimal place when the integer part is a
+        // single digit, and no decimal place once the integer part needs
+        // two digits or more. This only applies to the default
+        // one-decimal-place configuration; an explicitly requested
+        // precision is always honored as-is.
+        let precision = if self.precision == 1 && n / base_i >= 10 {
+            0
+        } else {
+            self.precision
+        };
+
+        // Round up to the chosen precision (GNU df always rounds a
+        // human-readable size up, never down, so usage is never
+        // under-reported) using integer arithmetic so this works
+        // uniformly for every `n` up to `u64::MAX`.
+        let scale = 10u128.pow(precision as u32);
+        let numerator = n * scale;
+        let scaled_quot = numerator / base_i;
+        let scaled_rem = numerator % base_i;
+        let rounded = if scaled_rem == 0 {
+            scaled_quot
+        } else {
+            scaled_quot + 1
+        };
+
+        let int_part = rounded / scale;
+        let frac_part = rounded % scale;
+
+        // Rounding up can carry all the way to a full next unit (e.g.
+        // 999.6kB rounds to 1000kB); bump to that unit when there is room.
+        if frac_part == 0 {
+            let (unit, int_part) = self.carry(i, int_part);
+            let suffix = self.unit_suffix(unit, int_part);
+            return if explicit_precision && precision > 0 {
+                Ok(format!(
+                    "{}.{:0width$}{}{}",
+                    int_part,
+                    0,
+                    self.separator,
+                    suffix,
+                    width = precision
+                ))
+            } else {
+                Ok(format!("{}{}{}", int_part, self.separator, suffix))
+            };
+        }
+
+        let suffix = self.unit_suffix(i, int_part);
+        Ok(format!(
+            "{}.{:0width$}{}{}",
+            int_part,
+            frac_part,
+            self.separator,
+            suffix,
+            width = precision
+        ))
+    }
+
+    /// If `magnitude` has reached a full multiple of the next unit, bump
+    /// the unit index and divide `magnitude` down accordingly. Bounds are
+    /// respected: no carry happens past the largest known unit.
+    fn carry(&self, unit: usize, magnitude: u128) -> (usize, u128) {
+        if magnitude >= self.base && unit + 1 < SUFFIXES.len() {
+            (unit + 1, magnitude / self.base)
+        } else {
+            (unit, magnitude)
         }
     }
 }
 
+impl Default for SizeFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Convert a number into a magnitude and a multi-byte unit suffix.
 ///
+/// Uses the powers-of-1024 divisor when `n` is an exact multiple of 1,024
+/// that is not also an exact multiple of 1,000; otherwise uses powers of
+/// 1,000.
+///
 /// # Errors
 ///
 /// If the number is too large to represent.
-fn to_magnitude_and_suffix(n: u128) -> Result<String, ()> {
-    if n % 1024 == 0 && n % 1000 != 0 {
-        to_magnitude_and_suffix_1024(n)
+fn to_magnitude_and_suffix(n: u128, suffix_type: SuffixType) -> Result<String, ()> {
+    let base = if n % 1024 == 0 && n % 1000 != 0 {
+        1024
     } else {
-        to_magnitude_and_suffix_not_powers_of_1024(n)
-    }
+        1000
+    };
+    SizeFormatter::new().base(base).suffix_type(suffix_type).format(n)
 }
 
 /// A mode to use in condensing the display of a large number of bytes.
@@ -153,7 +340,76 @@ pub(crate) enum HumanReadable {
     /// This variant represents powers of 1,024. Contrast with
     /// [`HumanReadable::Decimal`], which represents powers
     /// of 1,000.
-    Binary,
+    ///
+    /// The associated [`SuffixType`] selects between GNU `-h` style
+    /// single-letter suffixes and full IEC suffixes ("KiB", "MiB", ...).
+    Binary(SuffixType),
+}
+
+impl Default for HumanReadable {
+    /// The default `-h`/`--human-readable` mode: powers of 1,024 with
+    /// GNU's single-letter suffixes.
+    fn default() -> Self {
+        Self::Binary(SuffixType::Letter)
+    }
+}
+
+impl FromStr for HumanReadable {
+    type Err = ParseSizeError;
+
+    /// Parse the suffix spelling used by `-h`-style output: `"binary"` for
+    /// GNU's single-letter suffixes (the default), `"iec"` for
+    /// standards-conformant suffixes ("KiB", "MiB", ...), or `"decimal"`
+    /// for SI (1,000-based) units.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "binary" => Ok(Self::Binary(SuffixType::Letter)),
+            "iec" => Ok(Self::Binary(SuffixType::Iec)),
+            "decimal" => Ok(Self::Decimal),
+            _ => Err(ParseSizeError::ParseFailure(format!("{}", s.quote()))),
+        }
+    }
+}
+
+/// A byte count paired with the [`HumanReadable`] mode used to display it.
+///
+/// This is the bridge between the mode selected by `-h`/`--si` and the
+/// magnitude-and-suffix renderer in [`SizeFormatter`]: formatting one with
+/// `{}` is the site where the [`SuffixType`] carried by
+/// [`HumanReadable::Binary`] actually reaches output.
+pub(crate) struct HumanReadableSize {
+    bytes: u64,
+    mode: HumanReadable,
+}
+
+impl HumanReadableSize {
+    pub(crate) fn new(bytes: u64, mode: HumanReadable) -> Self {
+        Self { bytes, mode }
+    }
+}
+
+impl fmt::Display for HumanReadableSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // GNU df's `-h`/`--si` output uses one decimal place, no
+        // separator between the magnitude and the unit, and short unit
+        // names; spelled out explicitly here (rather than relying on
+        // `SizeFormatter::new()`'s defaults) so this is the one real call
+        // site that exercises the full builder surface.
+        let formatter = match self.mode {
+            HumanReadable::Decimal => SizeFormatter::new().base(1000),
+            HumanReadable::Binary(suffix_type) => {
+                SizeFormatter::new().base(1024).suffix_type(suffix_type)
+            }
+        }
+        .precision(1)
+        .separator("")
+        .long_units(false);
+
+        match formatter.format(self.bytes as u128) {
+            Ok(s) => write!(f, "{}", s),
+            Err(_) => Err(fmt::Error),
+        }
+    }
 }
 
 /// A block size to use in condensing the display of a large number of bytes.
@@ -179,6 +435,26 @@ impl BlockSize {
     }
 }
 
+impl Add for BlockSize {
+    type Output = Self;
+
+    /// Saturates at [`u64::MAX`] instead of panicking or silently
+    /// wrapping on overflow, since this is used to accumulate totals.
+    fn add(self, other: Self) -> Self {
+        Self::Bytes(self.as_u64().saturating_add(other.as_u64()))
+    }
+}
+
+impl Mul<u64> for BlockSize {
+    type Output = Self;
+
+    /// Saturates at [`u64::MAX`] instead of panicking or silently
+    /// wrapping on overflow, since this is used to accumulate totals.
+    fn mul(self, rhs: u64) -> Self {
+        Self::Bytes(self.as_u64().saturating_mul(rhs))
+    }
+}
+
 impl Default for BlockSize {
     fn default() -> Self {
         if env::var("POSIXLY_CORRECT").is_ok() {
@@ -189,25 +465,88 @@ impl Default for BlockSize {
     }
 }
 
+/// Environment variables that GNU df consults, in precedence order, when
+/// `--block-size` is not given on the command line.
+const BLOCK_SIZE_ENV_VARS: [&str; 3] = ["DF_BLOCK_SIZE", "BLOCK_SIZE", "BLOCKSIZE"];
+
+/// Parse a GNU-style block size string, like "1K", "1MiB", or "1kB".
+///
+/// A bare integer is taken as a number of bytes. An integer followed by a
+/// bare binary suffix (`K`, `M`, `G`, ...) or an explicit IEC suffix
+/// (`KiB`, `MiB`, `GiB`, ...) is a multiple of a power of 1024. An integer
+/// followed by an SI suffix (`kB`, `MB`, `GB`, ...) is a multiple of a
+/// power of 1000.
+///
+/// # Errors
+///
+/// Returns [`ParseSizeError::ParseFailure`] if `size` is empty, has an
+/// unrecognized suffix, does not start with a number, or evaluates to `0`.
+fn parse_block_size(size: &str) -> Result<u64, ParseSizeError> {
+    let fail = || ParseSizeError::ParseFailure(format!("{}", size.quote()));
+
+    let split_at = size.find(|c: char| !c.is_ascii_digit()).unwrap_or(size.len());
+    let (digits, suffix) = size.split_at(split_at);
+
+    if digits.is_empty() {
+        return Err(fail());
+    }
+    let magnitude: u128 = digits.parse().map_err(|_| fail())?;
+
+    let multiplier: u128 = match suffix {
+        "" | "B" => 1,
+        "K" | "KiB" => IEC_BASES[1],
+        "M" | "MiB" => IEC_BASES[2],
+        "G" | "GiB" => IEC_BASES[3],
+        "T" | "TiB" => IEC_BASES[4],
+        "P" | "PiB" => IEC_BASES[5],
+        "E" | "EiB" => IEC_BASES[6],
+        "Z" | "ZiB" => IEC_BASES[7],
+        "Y" | "YiB" => IEC_BASES[8],
+        "kB" => SI_BASES[1],
+        "MB" => SI_BASES[2],
+        "GB" => SI_BASES[3],
+        "TB" => SI_BASES[4],
+        "PB" => SI_BASES[5],
+        "EB" => SI_BASES[6],
+        "ZB" => SI_BASES[7],
+        "YB" => SI_BASES[8],
+        _ => return Err(fail()),
+    };
+
+    let bytes = magnitude.checked_mul(multiplier).and_then(|n| u64::try_from(n).ok());
+    match bytes {
+        Some(n) if n > 0 => Ok(n),
+        _ => Err(fail()),
+    }
+}
+
+impl FromStr for BlockSize {
+    type Err = ParseSizeError;
+
+    /// Parse a [`BlockSize`] using the same grammar as [`block_size_from_matches`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_block_size(s).map(Self::Bytes)
+    }
+}
+
 pub(crate) fn block_size_from_matches(matches: &ArgMatches) -> Result<BlockSize, ParseSizeError> {
-    if matches.is_present(OPT_BLOCKSIZE) {
-        let s = matches.value_of(OPT_BLOCKSIZE).unwrap();
-        let bytes = parse_size(s)?;
+    if let Some(s) = matches.value_of(OPT_BLOCKSIZE) {
+        return parse_block_size(s).map(BlockSize::Bytes);
+    }
 
-        if bytes > 0 {
-            Ok(BlockSize::Bytes(bytes))
-        } else {
-            Err(ParseSizeError::ParseFailure(format!("{}", s.quote())))
+    for var in BLOCK_SIZE_ENV_VARS {
+        if let Ok(s) = env::var(var) {
+            return parse_block_size(&s).map(BlockSize::Bytes);
         }
-    } else {
-        Ok(Default::default())
     }
+
+    Ok(Default::default())
 }
 
 impl fmt::Display for BlockSize {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Self::Bytes(n) => match to_magnitude_and_suffix(*n as u128) {
+            Self::Bytes(n) => match to_magnitude_and_suffix(*n as u128, SuffixType::Letter) {
                 Ok(s) => write!(f, "{}", s),
                 Err(_) => Err(fmt::Error),
             },
@@ -220,56 +559,170 @@ mod tests {
 
     use std::env;
 
-    use crate::blocks::{to_magnitude_and_suffix, BlockSize};
+    use std::str::FromStr;
+
+    use crate::blocks::{
+        parse_block_size, to_magnitude_and_suffix, BlockSize, HumanReadable, HumanReadableSize,
+        SizeFormatter, SuffixType,
+    };
 
     #[test]
     fn test_to_magnitude_and_suffix_powers_of_1024() {
-        assert_eq!(to_magnitude_and_suffix(1024).unwrap(), "1K");
-        assert_eq!(to_magnitude_and_suffix(2048).unwrap(), "2K");
-        assert_eq!(to_magnitude_and_suffix(4096).unwrap(), "4K");
-        assert_eq!(to_magnitude_and_suffix(1024 * 1024).unwrap(), "1M");
-        assert_eq!(to_magnitude_and_suffix(2 * 1024 * 1024).unwrap(), "2M");
-        assert_eq!(to_magnitude_and_suffix(1024 * 1024 * 1024).unwrap(), "1G");
+        assert_eq!(to_magnitude_and_suffix(1024, SuffixType::Letter).unwrap(), "1K");
+        assert_eq!(to_magnitude_and_suffix(2048, SuffixType::Letter).unwrap(), "2K");
+        assert_eq!(to_magnitude_and_suffix(4096, SuffixType::Letter).unwrap(), "4K");
+        assert_eq!(to_magnitude_and_suffix(1024 * 1024, SuffixType::Letter).unwrap(), "1M");
+        assert_eq!(to_magnitude_and_suffix(2 * 1024 * 1024, SuffixType::Letter).unwrap(), "2M");
+        assert_eq!(to_magnitude_and_suffix(1024 * 1024 * 1024, SuffixType::Letter).unwrap(), "1G");
         assert_eq!(
-            to_magnitude_and_suffix(34 * 1024 * 1024 * 1024).unwrap(),
+            to_magnitude_and_suffix(34 * 1024 * 1024 * 1024, SuffixType::Letter).unwrap(),
             "34G"
         );
     }
 
     #[test]
     fn test_to_magnitude_and_suffix_not_powers_of_1024() {
-        assert_eq!(to_magnitude_and_suffix(1).unwrap(), "1B");
-        assert_eq!(to_magnitude_and_suffix(999).unwrap(), "999B");
-
-        assert_eq!(to_magnitude_and_suffix(1000).unwrap(), "1kB");
-        assert_eq!(to_magnitude_and_suffix(1001).unwrap(), "1.1kB");
-        assert_eq!(to_magnitude_and_suffix(1023).unwrap(), "1.1kB");
-        assert_eq!(to_magnitude_and_suffix(1025).unwrap(), "1.1kB");
-        assert_eq!(to_magnitude_and_suffix(10_001).unwrap(), "11kB");
-        assert_eq!(to_magnitude_and_suffix(999_000).unwrap(), "999kB");
-
-        assert_eq!(to_magnitude_and_suffix(999_001).unwrap(), "1MB");
-        assert_eq!(to_magnitude_and_suffix(999_999).unwrap(), "1MB");
-        assert_eq!(to_magnitude_and_suffix(1_000_000).unwrap(), "1MB");
-        assert_eq!(to_magnitude_and_suffix(1_000_001).unwrap(), "1.1MB");
-        assert_eq!(to_magnitude_and_suffix(1_100_000).unwrap(), "1.1MB");
-        assert_eq!(to_magnitude_and_suffix(1_100_001).unwrap(), "1.2MB");
-        assert_eq!(to_magnitude_and_suffix(1_900_000).unwrap(), "1.9MB");
-        assert_eq!(to_magnitude_and_suffix(1_900_001).unwrap(), "2MB");
-        assert_eq!(to_magnitude_and_suffix(9_900_000).unwrap(), "9.9MB");
-        assert_eq!(to_magnitude_and_suffix(9_900_001).unwrap(), "10MB");
-        assert_eq!(to_magnitude_and_suffix(999_000_000).unwrap(), "999MB");
-
-        assert_eq!(to_magnitude_and_suffix(999_000_001).unwrap(), "1GB");
-        assert_eq!(to_magnitude_and_suffix(1_000_000_000).unwrap(), "1GB");
-        assert_eq!(to_magnitude_and_suffix(1_000_000_001).unwrap(), "1.1GB");
+        assert_eq!(to_magnitude_and_suffix(1, SuffixType::Letter).unwrap(), "1B");
+        assert_eq!(to_magnitude_and_suffix(999, SuffixType::Letter).unwrap(), "999B");
+
+        assert_eq!(to_magnitude_and_suffix(1000, SuffixType::Letter).unwrap(), "1kB");
+        assert_eq!(to_magnitude_and_suffix(1001, SuffixType::Letter).unwrap(), "1.1kB");
+        assert_eq!(to_magnitude_and_suffix(1023, SuffixType::Letter).unwrap(), "1.1kB");
+        assert_eq!(to_magnitude_and_suffix(1025, SuffixType::Letter).unwrap(), "1.1kB");
+        assert_eq!(to_magnitude_and_suffix(10_001, SuffixType::Letter).unwrap(), "11kB");
+        assert_eq!(to_magnitude_and_suffix(999_000, SuffixType::Letter).unwrap(), "999kB");
+
+        assert_eq!(to_magnitude_and_suffix(999_001, SuffixType::Letter).unwrap(), "1MB");
+        assert_eq!(to_magnitude_and_suffix(999_999, SuffixType::Letter).unwrap(), "1MB");
+        assert_eq!(to_magnitude_and_suffix(1_000_000, SuffixType::Letter).unwrap(), "1MB");
+        assert_eq!(to_magnitude_and_suffix(1_000_001, SuffixType::Letter).unwrap(), "1.1MB");
+        assert_eq!(to_magnitude_and_suffix(1_100_000, SuffixType::Letter).unwrap(), "1.1MB");
+        assert_eq!(to_magnitude_and_suffix(1_100_001, SuffixType::Letter).unwrap(), "1.2MB");
+        assert_eq!(to_magnitude_and_suffix(1_900_000, SuffixType::Letter).unwrap(), "1.9MB");
+        assert_eq!(to_magnitude_and_suffix(1_900_001, SuffixType::Letter).unwrap(), "2MB");
+        assert_eq!(to_magnitude_and_suffix(9_900_000, SuffixType::Letter).unwrap(), "9.9MB");
+        assert_eq!(to_magnitude_and_suffix(9_900_001, SuffixType::Letter).unwrap(), "10MB");
+        assert_eq!(to_magnitude_and_suffix(999_000_000, SuffixType::Letter).unwrap(), "999MB");
+
+        assert_eq!(to_magnitude_and_suffix(999_000_001, SuffixType::Letter).unwrap(), "1GB");
+        assert_eq!(to_magnitude_and_suffix(1_000_000_000, SuffixType::Letter).unwrap(), "1GB");
+        assert_eq!(to_magnitude_and_suffix(1_000_000_001, SuffixType::Letter).unwrap(), "1.1GB");
+    }
+
+    #[test]
+    fn test_to_magnitude_and_suffix_exabyte_range() {
+        assert_eq!(
+            to_magnitude_and_suffix(5_000_000_000_000_000_000, SuffixType::Letter).unwrap(),
+            "5EB"
+        );
+        assert_eq!(
+            to_magnitude_and_suffix(5_500_000_000_000_000_000, SuffixType::Letter).unwrap(),
+            "5.5EB"
+        );
+        // No out-of-bounds access, and rounding still carries correctly,
+        // for the largest possible `u64`.
+        assert_eq!(
+            to_magnitude_and_suffix(u64::MAX as u128, SuffixType::Letter).unwrap(),
+            "19EB"
+        );
     }
 
     #[test]
     fn test_to_magnitude_and_suffix_multiples_of_1000_and_1024() {
-        assert_eq!(to_magnitude_and_suffix(128_000).unwrap(), "128kB");
-        assert_eq!(to_magnitude_and_suffix(1000 * 1024).unwrap(), "1.1MB");
-        assert_eq!(to_magnitude_and_suffix(1_000_000_000_000).unwrap(), "1TB");
+        assert_eq!(to_magnitude_and_suffix(128_000, SuffixType::Letter).unwrap(), "128kB");
+        assert_eq!(to_magnitude_and_suffix(1000 * 1024, SuffixType::Letter).unwrap(), "1.1MB");
+        assert_eq!(to_magnitude_and_suffix(1_000_000_000_000, SuffixType::Letter).unwrap(), "1TB");
+    }
+
+    #[test]
+    fn test_to_magnitude_and_suffix_iec() {
+        assert_eq!(to_magnitude_and_suffix(1024, SuffixType::Iec).unwrap(), "1KiB");
+        assert_eq!(
+            to_magnitude_and_suffix(1024 * 1024, SuffixType::Iec).unwrap(),
+            "1MiB"
+        );
+        assert_eq!(
+            to_magnitude_and_suffix(1024 * 1024 * 1024, SuffixType::Iec).unwrap(),
+            "1GiB"
+        );
+    }
+
+    #[test]
+    fn test_size_formatter_precision_and_separator() {
+        let formatter = SizeFormatter::new().base(1000).precision(3).separator(" ");
+        assert_eq!(formatter.format(1_049_000).unwrap(), "1.049 MB");
+    }
+
+    #[test]
+    fn test_size_formatter_explicit_precision_pads_whole_numbers() {
+        let formatter = SizeFormatter::new().base(1000).precision(3);
+        assert_eq!(formatter.format(2_000_000).unwrap(), "2.000MB");
+
+        // A rounding carry that lands on an exact unit is padded too.
+        let formatter = SizeFormatter::new().base(1000).precision(2);
+        assert_eq!(formatter.format(999_999).unwrap(), "1.00MB");
+
+        // The default precision keeps its existing compacting behavior.
+        let formatter = SizeFormatter::new().base(1000);
+        assert_eq!(formatter.format(2_000_000).unwrap(), "2MB");
+
+        // An explicit precision of 0 means no decimal point at all, not
+        // a trailing ".0".
+        let formatter = SizeFormatter::new().base(1000).precision(0);
+        assert_eq!(formatter.format(2_000_000).unwrap(), "2MB");
+        assert_eq!(formatter.format(1_999_999).unwrap(), "2MB");
+    }
+
+    #[test]
+    fn test_size_formatter_long_units() {
+        let formatter = SizeFormatter::new().base(1000).long_units(true).separator(" ");
+        assert_eq!(formatter.format(1).unwrap(), "1 byte");
+        assert_eq!(formatter.format(2).unwrap(), "2 bytes");
+        assert_eq!(formatter.format(1_000_000).unwrap(), "1 Megabytes");
+    }
+
+    #[test]
+    fn test_human_readable_from_str() {
+        assert!(matches!(
+            HumanReadable::from_str("binary").unwrap(),
+            HumanReadable::Binary(SuffixType::Letter)
+        ));
+        assert!(matches!(
+            HumanReadable::from_str("iec").unwrap(),
+            HumanReadable::Binary(SuffixType::Iec)
+        ));
+        assert!(matches!(
+            HumanReadable::from_str("decimal").unwrap(),
+            HumanReadable::Decimal
+        ));
+        assert!(HumanReadable::from_str("bogus").is_err());
+    }
+
+    #[test]
+    fn test_human_readable_default() {
+        assert!(matches!(
+            HumanReadable::default(),
+            HumanReadable::Binary(SuffixType::Letter)
+        ));
+    }
+
+    #[test]
+    fn test_human_readable_size_display() {
+        assert_eq!(
+            format!("{}", HumanReadableSize::new(1024, HumanReadable::default())),
+            "1K"
+        );
+        assert_eq!(
+            format!(
+                "{}",
+                HumanReadableSize::new(1024, HumanReadable::Binary(SuffixType::Iec))
+            ),
+            "1KiB"
+        );
+        assert_eq!(
+            format!("{}", HumanReadableSize::new(1_000_000, HumanReadable::Decimal)),
+            "1MB"
+        );
     }
 
     #[test]
@@ -286,4 +739,66 @@ mod tests {
         assert_eq!(BlockSize::Bytes(512), BlockSize::default());
         env::remove_var("POSIXLY_CORRECT");
     }
+
+    #[test]
+    fn test_parse_block_size_bare_integer() {
+        assert_eq!(parse_block_size("1").unwrap(), 1);
+        assert_eq!(parse_block_size("1024").unwrap(), 1024);
+    }
+
+    #[test]
+    fn test_parse_block_size_binary_suffix() {
+        assert_eq!(parse_block_size("1K").unwrap(), 1024);
+        assert_eq!(parse_block_size("1KiB").unwrap(), 1024);
+        assert_eq!(parse_block_size("1M").unwrap(), 1024 * 1024);
+        assert_eq!(parse_block_size("1MiB").unwrap(), 1024 * 1024);
+        assert_eq!(parse_block_size("1GiB").unwrap(), 1024 * 1024 * 1024);
+    }
+
+    #[test]
+    fn test_parse_block_size_si_suffix() {
+        assert_eq!(parse_block_size("1kB").unwrap(), 1000);
+        assert_eq!(parse_block_size("1MB").unwrap(), 1_000_000);
+        assert_eq!(parse_block_size("1GB").unwrap(), 1_000_000_000);
+    }
+
+    #[test]
+    fn test_parse_block_size_errors() {
+        assert!(parse_block_size("0").is_err());
+        assert!(parse_block_size("").is_err());
+        assert!(parse_block_size("1XB").is_err());
+        assert!(parse_block_size("abc").is_err());
+    }
+
+    #[test]
+    fn test_block_size_from_str() {
+        assert_eq!(BlockSize::from_str("1024").unwrap(), BlockSize::Bytes(1024));
+        assert_eq!(BlockSize::from_str("1K").unwrap(), BlockSize::Bytes(1024));
+        assert_eq!(BlockSize::from_str("1MiB").unwrap(), BlockSize::Bytes(1024 * 1024));
+        assert_eq!(BlockSize::from_str("1kB").unwrap(), BlockSize::Bytes(1000));
+        assert!(BlockSize::from_str("0").is_err());
+    }
+
+    #[test]
+    fn test_block_size_arithmetic() {
+        assert_eq!(
+            BlockSize::Bytes(512) + BlockSize::Bytes(512),
+            BlockSize::Bytes(1024)
+        );
+        assert_eq!(BlockSize::Bytes(512) * 4, BlockSize::Bytes(2048));
+    }
+
+    #[test]
+    fn test_block_size_arithmetic_saturates_on_overflow() {
+        assert_eq!(
+            BlockSize::Bytes(u64::MAX) + BlockSize::Bytes(1),
+            BlockSize::Bytes(u64::MAX)
+        );
+        assert_eq!(BlockSize::Bytes(u64::MAX) * 2, BlockSize::Bytes(u64::MAX));
+    }
+
+    #[test]
+    fn test_block_size_display_past_exabytes() {
+        assert_eq!(format!("{}", BlockSize::Bytes(u64::MAX)), "19EB");
+    }
 }